@@ -0,0 +1,17 @@
+use crate::crypto::PublicKey;
+use crate::grpc::{consensus_message::MessageOneof, ConsensusMessage, Signature};
+use prost::Message;
+
+/// Verify a `Signature` over `message`, supposedly from `signer`.
+/// If `message` carries a `Ballot` with a timestamp, the ballot's own timestamp is
+/// checked against `signer`'s certificate validity window (via
+/// `PublicKey::verify_signature_at`), so a message signed under an expired or
+/// not-yet-valid certificate is rejected rather than just checking the signature math.
+pub fn verify(signer : &PublicKey, message : &ConsensusMessage, signature : Signature) -> bool {
+    match &message.message_oneof {
+        Some(MessageOneof::Ballot(ballot)) if ballot.timestamp.is_some() =>
+            signer.verify_signature_at(&message.encode_to_vec()[..], signature.bytes,
+                                       ballot.timestamp.clone().unwrap()),
+        _ => signer.verify_signature(message, signature),
+    }
+}
@@ -0,0 +1,3 @@
+/// Learners check the same signature/validity-window conditions as acceptors when
+/// deciding whether to trust a message from a participant.
+pub use crate::acceptor::verify;
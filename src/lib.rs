@@ -23,6 +23,31 @@ pub mod grpc {
             }
         }
     }
+
+    /// A `Ballot` that (de)serializes its timestamp as an RFC 3339 string (via
+    /// `crate::utils::timestamp_rfc3339`) instead of the raw `{seconds, nanos}` object
+    /// the generated `Ballot` type above uses. `Ballot` itself comes from `.proto`
+    /// codegen, so a field attribute can't be added to it directly in this crate;
+    /// this newtype is where `#[serde(with = "...")]` is actually applied, and is
+    /// what JSON config files/logs should serialize `Ballot`s through.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub struct BallotJson {
+        #[serde(with = "crate::utils::timestamp_rfc3339")]
+        pub timestamp : Option<pbjson_types::Timestamp>,
+        pub value_hash : Option<Hash256>,
+    }
+
+    impl From<Ballot> for BallotJson {
+        fn from(ballot : Ballot) -> Self {
+            BallotJson { timestamp : ballot.timestamp, value_hash : ballot.value_hash }
+        }
+    }
+
+    impl From<BallotJson> for Ballot {
+        fn from(json : BallotJson) -> Self {
+            Ballot { timestamp : json.timestamp, value_hash : json.value_hash }
+        }
+    }
 }
 
 /// Include generated code from `proto/hetpaxosrefconfig.proto`.
@@ -41,6 +66,35 @@ pub mod utils {
     use sha3::{Digest, Sha3_256};
     use std::{fmt::{Display, Formatter, Result}, hash::{Hash, Hasher}, cmp::Ordering};
 
+    /// Days since the Unix epoch for a (proleptic Gregorian) calendar date.
+    /// Howard Hinnant's well-known `days_from_civil` algorithm.
+    /// Shared by `crypto`'s certificate validity parsing and `timestamp_rfc3339` below,
+    /// so the two don't drift out of sync with separate copies of the same math.
+    pub(crate) fn days_from_civil(y : i64, m : i64, d : i64) -> i64 {
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = if y >= 0 { y } else { y - 399 } / 400;
+        let yoe = y - era * 400;
+        let mp = (m + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    /// The inverse of `days_from_civil`: the calendar date for a given number of days
+    /// since the Unix epoch.
+    pub(crate) fn civil_from_days(z : i64) -> (i64, i64, i64) {
+        let z = z + 719468;
+        let era = if z >= 0 { z } else { z - 146096 } / 146097;
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let y = yoe + era * 400;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let d = doy - (153 * mp + 2) / 5 + 1;
+        let m = if mp < 10 { mp + 3 } else { mp - 9 };
+        (if m <= 2 { y + 1 } else { y }, m, d)
+    }
+
     /// Hash a protobuf Message struct with Sha3 into a Hash256 struct.
     /// Bytes marshaled in BigEndian order.
     pub fn hash(message : &impl Message) -> Hash256 {
@@ -103,30 +157,176 @@ pub mod utils {
 
     impl Eq for Ballot {}
 
+    /// Turn a `Ballot`'s `Option<Timestamp>` into something `Ord`, with `None` sorting
+    /// strictly below every present timestamp (rather than colliding with a timestamp
+    /// of `seconds == 0, nanos == 0`).
+    fn timestamp_key(timestamp : &Option<Timestamp>) -> Option<(i64, i32)> {
+        timestamp.as_ref().map(|t| (t.seconds, t.nanos))
+    }
+
     /// We want to be able to compare ballots using < etc.
     impl Ord for Ballot {
-        /// Ordering is by timestamp (0 used if no Timestamp is available), then value hash.
+        /// Ordering is by timestamp (with no timestamp sorting below any present
+        /// timestamp), then value hash.
         fn cmp(&self, other: &Self) -> Ordering {
-            fn timestamp_tuple(timestamp : &Option<Timestamp>) -> (i64, i32) {
-                if let Some(t) = timestamp {
-                   (t.seconds, t.nanos)
-                } else {
-                   (0, 0)
-                }
-            }
-            (timestamp_tuple(&self.timestamp), &self.value_hash).cmp(
-             &(timestamp_tuple(&other.timestamp), &other.value_hash))
+            (timestamp_key(&self.timestamp), &self.value_hash).cmp(
+             &(timestamp_key(&other.timestamp), &other.value_hash))
         }
     }
 
     /// We want to be able to compare ballots using < etc.
     impl PartialOrd for Ballot {
-        /// Ordering is by timestamp (0 used if no Timestamp is available), then value hash.
-        /// This uses `Ballot`'s `Ord` implementation.
+        /// Ordering is by timestamp (with no timestamp sorting below any present
+        /// timestamp), then value hash. This uses `Ballot`'s `Ord` implementation.
         fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
             Some(self.cmp(other))
         }
     }
+
+    /// Serde `with`-style (de)serialization of an `Option<Timestamp>` as an RFC 3339 /
+    /// ISO 8601 string (e.g. `"2024-05-01T12:00:00.123Z"`), with fractional-second
+    /// support, instead of the raw `{seconds, nanos}` object pbjson generates by
+    /// default. Use it on a field with `#[serde(with = "crate::utils::timestamp_rfc3339")]`.
+    pub mod timestamp_rfc3339 {
+        use super::{civil_from_days, days_from_civil};
+        use pbjson_types::Timestamp;
+        use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+
+        /// Format a `Timestamp` as `YYYY-MM-DDTHH:MM:SS[.fffffffff]Z`, omitting the
+        /// fractional part entirely when `nanos == 0`. The fractional part, when
+        /// present, is always the full 9-digit nanosecond count (not truncated to
+        /// milliseconds), so `parse_rfc3339` round-trips any `nanos` value exactly.
+        fn format_rfc3339(t : &Timestamp) -> String {
+            let days = t.seconds.div_euclid(86400);
+            let time_of_day = t.seconds.rem_euclid(86400);
+            let (year, month, day) = civil_from_days(days);
+            let (hour, minute, second) = (time_of_day / 3600, (time_of_day / 60) % 60, time_of_day % 60);
+            let mut out = format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                                   year, month, day, hour, minute, second);
+            if t.nanos != 0 {
+                out.push_str(&format!(".{:09}", t.nanos));
+            }
+            out.push('Z');
+            out
+        }
+
+        /// Parse an RFC 3339 string of the form `YYYY-MM-DDTHH:MM:SS[.fraction]Z` into
+        /// a `Timestamp`. Only the UTC (`Z`-suffixed) form is accepted.
+        fn parse_rfc3339(s : &str) -> Result<Timestamp, String> {
+            let s = s.strip_suffix('Z')
+                .ok_or_else(|| format!("timestamp {} is not UTC (must end in 'Z')", s))?;
+            let (date, time) = s.split_once('T')
+                .ok_or_else(|| format!("timestamp {} is missing its 'T' date/time separator", s))?;
+            let bad = || format!("timestamp {} is not a valid RFC 3339 timestamp", s);
+            let mut date_parts = date.splitn(3, '-');
+            let year : i64 = date_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let month : i64 = date_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let day : i64 = date_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let (time, fraction) = match time.split_once('.') {
+                Some((t, f)) => (t, Some(f)),
+                None => (time, None),
+            };
+            let mut time_parts = time.splitn(3, ':');
+            let hour : i64 = time_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let minute : i64 = time_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let second : i64 = time_parts.next().ok_or_else(bad)?.parse().map_err(|_| bad())?;
+            let nanos : i32 = match fraction {
+                Some(f) => {
+                    let padded = format!("{:0<9}", &f[..f.len().min(9)]);
+                    padded.parse().map_err(|_| bad())?
+                }
+                None => 0,
+            };
+            let seconds = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+            Ok(Timestamp { seconds, nanos })
+        }
+
+        pub fn serialize<S : Serializer>(timestamp : &Option<Timestamp>, serializer : S)
+                -> Result<S::Ok, S::Error> {
+            match timestamp {
+                Some(t) => format_rfc3339(t).serialize(serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D : Deserializer<'de>>(deserializer : D)
+                -> Result<Option<Timestamp>, D::Error> {
+            let opt = Option::<String>::deserialize(deserializer)?;
+            opt.map(|s| parse_rfc3339(&s).map_err(DeError::custom)).transpose()
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::{format_rfc3339, parse_rfc3339};
+            use pbjson_types::Timestamp;
+
+            #[test]
+            fn round_trips_whole_seconds() {
+                let t = Timestamp { seconds : 1_714_564_800, nanos : 0 };
+                assert_eq!(parse_rfc3339(&format_rfc3339(&t)).unwrap(), t);
+            }
+
+            #[test]
+            fn round_trips_sub_second_precision() {
+                let t = Timestamp { seconds : 1_714_564_800, nanos : 123_000_000 };
+                let formatted = format_rfc3339(&t);
+                assert_eq!(formatted, "2024-05-01T12:00:00.123000000Z");
+                assert_eq!(parse_rfc3339(&formatted).unwrap(), t);
+            }
+
+            #[test]
+            fn round_trips_non_millisecond_aligned_nanos() {
+                // Regression test: `format_rfc3339` used to truncate to millisecond
+                // precision (`nanos / 1_000_000`), so a value like this silently lost
+                // its last 6 digits and didn't round-trip.
+                let t = Timestamp { seconds : 1_714_564_800, nanos : 123_456_789 };
+                let formatted = format_rfc3339(&t);
+                assert_eq!(formatted, "2024-05-01T12:00:00.123456789Z");
+                assert_eq!(parse_rfc3339(&formatted).unwrap(), t);
+            }
+
+            #[test]
+            fn rejects_non_utc_timestamps() {
+                assert!(parse_rfc3339("2024-05-01T12:00:00+01:00").is_err());
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{timestamp_key, Ballot, Hash256};
+
+        fn ballot(seconds : Option<i64>, value_hash : Hash256) -> Ballot {
+            Ballot {
+                timestamp : seconds.map(|seconds| pbjson_types::Timestamp { seconds, nanos : 0 }),
+                value_hash : Some(value_hash),
+            }
+        }
+
+        fn hash(n : u64) -> Hash256 {
+            Hash256 { bytes0_through7 : n, bytes8_through15 : 0, bytes16_through23 : 0,
+                       bytes24_through31 : 0 }
+        }
+
+        #[test]
+        fn missing_timestamp_sorts_below_any_present_timestamp() {
+            assert!(ballot(None, hash(0)) < ballot(Some(0), hash(0)));
+            assert!(timestamp_key(&None) < timestamp_key(&Some(pbjson_types::Timestamp::default())));
+        }
+
+        #[test]
+        fn sub_second_nanos_are_ordered() {
+            let earlier = Ballot {
+                timestamp : Some(pbjson_types::Timestamp { seconds : 10, nanos : 1 }),
+                value_hash : Some(hash(0)),
+            };
+            let later = Ballot {
+                timestamp : Some(pbjson_types::Timestamp { seconds : 10, nanos : 2 }),
+                value_hash : Some(hash(0)),
+            };
+            assert!(earlier < later);
+        }
+    }
 }
 
 
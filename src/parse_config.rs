@@ -0,0 +1,22 @@
+use crate::crypto::{keys_from_strings, PrivateKey, PublicKey};
+
+/// One participant's identity as read from a config file: a hostname, and the
+/// PEM-encoded public/private key strings for it.
+pub struct ParticipantConfig {
+    pub hostname : String,
+    pub public_key_string : String,
+    pub private_key_string : String,
+}
+
+/// Parse every participant's key pair out of `participants`, failing fast with an
+/// error identifying the offending host if any private key doesn't match its public
+/// key (see `crypto::keys_from_strings`), rather than failing mysteriously later at
+/// consensus time.
+pub fn parse_config(participants : &[ParticipantConfig])
+        -> Result<Vec<(PublicKey, PrivateKey)>, String> {
+    participants.iter().map(|participant| {
+        keys_from_strings(participant.public_key_string.clone(),
+                          participant.private_key_string.clone())
+            .map_err(|e| format!("host {}: {}", participant.hostname, e))
+    }).collect()
+}